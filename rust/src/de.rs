@@ -0,0 +1,346 @@
+//! Deserialize arbitrary Rust types from NEON.
+//!
+//! The counterparts to [`crate::ser`]: a first-class [`Deserializer`] drives
+//! serde's visitor API directly off the decoded NEON structure, so the
+//! [`from_str`]/[`from_reader`] entry points never route back through
+//! `serde_json::from_value`. Shape mismatches surface as [`NeonError::Type`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! struct User { id: u32, name: String, active: bool }
+//!
+//! let user: User = neon::de::from_str("@id:1 name:Alice active:T", None).unwrap();
+//! assert_eq!(user.id, 1);
+//! ```
+
+use crate::decoder::decode;
+use crate::error::{NeonError, Result};
+use crate::types::NeonDecodeOptions;
+use serde::de::{
+    self, DeserializeOwned, Deserializer as _, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde_json::Value;
+use std::io::Read;
+use std::vec;
+
+/// Deserialize a `T` from a NEON string.
+pub fn from_str<T>(input: &str, options: Option<NeonDecodeOptions>) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let value = decode(input, options)?;
+    T::deserialize(Deserializer::new(value))
+}
+
+/// Deserialize a `T` from any [`io::Read`](std::io::Read) source of NEON.
+pub fn from_reader<R, T>(mut reader: R, options: Option<NeonDecodeOptions>) -> Result<T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let mut buffer = String::new();
+    reader.read_to_string(&mut buffer)?;
+    from_str(&buffer, options)
+}
+
+/// A [`serde::Deserializer`] over a decoded NEON [`Value`]. Implementing it here
+/// keeps `serde_json`'s deserializer off the hot path.
+pub struct Deserializer {
+    value: Value,
+}
+
+impl Deserializer {
+    /// Wrap an already-decoded NEON value for deserialization.
+    pub fn new(value: Value) -> Self {
+        Deserializer { value }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = NeonError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Number(n) => {
+                if let Some(u) = n.as_u64() {
+                    visitor.visit_u64(u)
+                } else if let Some(i) = n.as_i64() {
+                    visitor.visit_i64(i)
+                } else if let Some(f) = n.as_f64() {
+                    visitor.visit_f64(f)
+                } else {
+                    Err(NeonError::type_error("unrepresentable number"))
+                }
+            }
+            Value::String(s) => visitor.visit_string(s),
+            Value::Array(arr) => {
+                let mut seq = SeqDeserializer {
+                    iter: arr.into_iter(),
+                };
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Object(obj) => {
+                let mut map = MapDeserializer {
+                    iter: obj.into_iter(),
+                    value: None,
+                };
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            // Unit variant: a bare string naming the variant.
+            Value::String(variant) => visitor.visit_enum(EnumRef {
+                variant,
+                value: None,
+            }),
+            // Other variants: a single-key `{variant: payload}` object.
+            Value::Object(obj) => {
+                let mut iter = obj.into_iter();
+                let (variant, value) = match iter.next() {
+                    Some(pair) => pair,
+                    None => {
+                        return Err(NeonError::type_error(
+                            "expected a single-key object for an enum variant",
+                        ))
+                    }
+                };
+                if iter.next().is_some() {
+                    return Err(NeonError::type_error(
+                        "expected a single-key object for an enum variant",
+                    ));
+                }
+                visitor.visit_enum(EnumRef {
+                    variant,
+                    value: Some(value),
+                })
+            }
+            _ => Err(NeonError::type_error("expected an enum variant")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct SeqDeserializer {
+    iter: vec::IntoIter<Value>,
+}
+
+impl SeqDeserializer {
+    fn end(self) -> Result<()> {
+        if self.iter.len() == 0 {
+            Ok(())
+        } else {
+            Err(NeonError::type_error("trailing sequence elements"))
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = NeonError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(Deserializer::new(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapDeserializer {
+    iter: serde_json::map::IntoIter,
+    value: Option<Value>,
+}
+
+impl MapDeserializer {
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = NeonError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| NeonError::type_error("value is missing"))?;
+        seed.deserialize(Deserializer::new(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        }
+    }
+}
+
+struct EnumRef {
+    variant: String,
+    value: Option<Value>,
+}
+
+impl<'de> EnumAccess<'de> for EnumRef {
+    type Error = NeonError;
+    type Variant = VariantRef;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, VariantRef { value: self.value }))
+    }
+}
+
+struct VariantRef {
+    value: Option<Value>,
+}
+
+impl<'de> VariantAccess<'de> for VariantRef {
+    type Error = NeonError;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value {
+            None => Ok(()),
+            Some(_) => Err(NeonError::type_error("expected a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(Deserializer::new(value)),
+            None => Err(NeonError::type_error("expected a newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) => Deserializer::new(value).deserialize_seq(visitor),
+            None => Err(NeonError::type_error("expected a tuple variant")),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) => Deserializer::new(value).deserialize_map(visitor),
+            None => Err(NeonError::type_error("expected a struct variant")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct User {
+        id: u32,
+        name: String,
+        active: bool,
+    }
+
+    #[test]
+    fn test_from_str_struct() {
+        let user: User = from_str("@id:1 name:Alice active:T", None).unwrap();
+        assert_eq!(
+            user,
+            User {
+                id: 1,
+                name: "Alice".into(),
+                active: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_str_seq() {
+        let nums: Vec<i64> = from_str("#3 1 2 3", None).unwrap();
+        assert_eq!(nums, vec![1, 2, 3]);
+    }
+}
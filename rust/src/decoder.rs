@@ -1,8 +1,14 @@
 //! NEON Decoder Implementation
 
 use crate::error::{NeonError, Result};
-use crate::types::{symbols, get_expansions, NeonDecodeOptions, NeonStats, Token, TokenType};
+use crate::types::{
+    get_expansions, symbols, FieldType, NeonDecodeOptions, NeonStats, SchemaFields, Token,
+    TokenType,
+};
 use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
 use std::time::Instant;
 
 /// Expand abbreviated numbers
@@ -36,6 +42,78 @@ fn expand_number(s: &str) -> f64 {
     s.parse().unwrap_or(0.0)
 }
 
+/// Parse a number token's raw text into a precision-preserving [`Value`].
+///
+/// Used when `preserve_numbers` is set: integers keep their full `i64`/`u64`
+/// range instead of being routed through `f64`, and suffix-compressed values
+/// (`2.5M`) are expanded back to their exact integer.
+fn parse_number_exact(raw: &str) -> Value {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Value::Number(Number::from(0));
+    }
+
+    let (mag, body): (u128, &str) = match raw.chars().last() {
+        Some('T') => (1_000_000_000_000, &raw[..raw.len() - 1]),
+        Some('B') => (1_000_000_000, &raw[..raw.len() - 1]),
+        Some('M') => (1_000_000, &raw[..raw.len() - 1]),
+        Some('K') => (1_000, &raw[..raw.len() - 1]),
+        _ => (1, raw),
+    };
+
+    if mag > 1 {
+        // Suffix values carry at most one decimal place (see the encoder), so
+        // `body * mag` is an exact integer.
+        if let Ok(scaled) = (body.parse::<f64>()).map(|v| v * mag as f64) {
+            if scaled.fract() == 0.0 {
+                return int_value(scaled as i128);
+            }
+        }
+        return Value::Number(Number::from_f64(expand_number(raw)).unwrap_or(Number::from(0)));
+    }
+
+    // No suffix: keep exact integers, then fall back to float.
+    if let Ok(u) = raw.parse::<u64>() {
+        return Value::Number(Number::from(u));
+    }
+    if let Ok(i) = raw.parse::<i64>() {
+        return Value::Number(Number::from(i));
+    }
+
+    let normalized = if let Some(rest) = raw.strip_prefix('.') {
+        format!("0.{}", rest)
+    } else if let Some(rest) = raw.strip_prefix("-.") {
+        format!("-0.{}", rest)
+    } else {
+        raw.to_string()
+    };
+    match normalized.parse::<f64>() {
+        Ok(f) => Value::Number(Number::from_f64(f).unwrap_or(Number::from(0))),
+        Err(_) => Value::Number(Number::from(0)),
+    }
+}
+
+/// Build a JSON number from an `i128`, choosing the widest exact integer type.
+fn int_value(v: i128) -> Value {
+    if v >= 0 {
+        Value::Number(Number::from(v as u64))
+    } else {
+        Value::Number(Number::from(v as i64))
+    }
+}
+
+/// Name of a decoded value's JSON kind, for type-mismatch diagnostics.
+fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
 /// Lexer for NEON format
 struct Lexer {
     input: Vec<char>,
@@ -115,6 +193,28 @@ impl Lexer {
                 self.advance();
                 return;
             }
+            c if c == symbols::TYPE_PREFIX => {
+                self.add_token(TokenType::TypePrefix, c.to_string(), c.to_string());
+                self.advance();
+                return;
+            }
+            c if c == symbols::COLUMNAR => {
+                self.add_token(TokenType::Columnar, c.to_string(), c.to_string());
+                self.advance();
+                return;
+            }
+            c if c == symbols::SCHEMA_REF => {
+                self.scan_schema_ref();
+                return;
+            }
+            c if c == symbols::REFERENCE => {
+                self.scan_reference();
+                return;
+            }
+            c if c == symbols::PATH => {
+                self.scan_path();
+                return;
+            }
             _ => {}
         }
 
@@ -217,9 +317,36 @@ impl Lexer {
         self.add_token(TokenType::Number, value, raw);
     }
 
+    fn scan_reference(&mut self) {
+        let start = self.pos;
+        self.advance(); // Skip '$'
+        let id_start = self.pos;
+        while self.pos < self.input.len() && !self.is_word_boundary(self.pos) {
+            self.advance();
+        }
+        let id: String = self.input[id_start..self.pos].iter().collect();
+        let raw: String = self.input[start..self.pos].iter().collect();
+        self.add_token(TokenType::Reference, id, raw);
+    }
+
+    fn scan_path(&mut self) {
+        let start = self.pos;
+        self.advance(); // Skip '~'
+        let path_start = self.pos;
+        // A path is a run of JSON-Pointer-style segments; '/' separates them
+        // and is not a delimiter, so only whitespace and structural symbols end it.
+        let delimiters = " \t\n\r:,#@$~^>|\"";
+        while self.pos < self.input.len() && !delimiters.contains(self.input[self.pos]) {
+            self.advance();
+        }
+        let path: String = self.input[path_start..self.pos].iter().collect();
+        let raw: String = self.input[start..self.pos].iter().collect();
+        self.add_token(TokenType::Path, path, raw);
+    }
+
     fn scan_unquoted_string(&mut self) {
         let start = self.pos;
-        let delimiters = " \t\n\r:,#@$~^>\"";
+        let delimiters = " \t\n\r:,#@$~^>|\"";
 
         while self.pos < self.input.len() && !delimiters.contains(self.input[self.pos]) {
             self.advance();
@@ -240,6 +367,27 @@ impl Lexer {
         }
     }
 
+    /// Scan a `§` schema reference: the marker followed by the registry index.
+    /// A trailing `=` (as in the `§n=fields` preamble) is consumed here so the
+    /// field list that follows lexes as ordinary schema tokens; a bare `§n`
+    /// reference leaves the following newline untouched.
+    fn scan_schema_ref(&mut self) {
+        self.advance(); // consume `§`
+        let start = self.pos;
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_digit() {
+            self.advance();
+        }
+        let index: String = self.input[start..self.pos].iter().collect();
+        self.add_token(
+            TokenType::SchemaRef,
+            index.clone(),
+            format!("{}{}", symbols::SCHEMA_REF, index),
+        );
+        if self.pos < self.input.len() && self.input[self.pos] == '=' {
+            self.advance();
+        }
+    }
+
     fn is_number_start(&self, c: char) -> bool {
         c.is_ascii_digit() || c == '-' || c == '.'
     }
@@ -248,7 +396,7 @@ impl Lexer {
         if pos >= self.input.len() {
             return true;
         }
-        let delimiters = " \t\n\r:,#@$~^>\"";
+        let delimiters = " \t\n\r:,#@$~^>|\"";
         delimiters.contains(self.input[pos])
     }
 
@@ -268,14 +416,73 @@ impl Lexer {
     }
 }
 
+/// A single decision the parser made, recorded for [`DecodeTrace`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent {
+    ObjectStart,
+    ArrayLength(usize),
+    Schema(Vec<String>),
+    TabularRows(usize),
+    ListRows(usize),
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::ObjectStart => write!(f, "(object)"),
+            TraceEvent::ArrayLength(n) => write!(f, "(array len={})", n),
+            TraceEvent::Schema(fields) => write!(f, "(schema {})", fields.join(",")),
+            TraceEvent::TabularRows(n) => write!(f, "(tabular rows={})", n),
+            TraceEvent::ListRows(n) => write!(f, "(list rows={})", n),
+        }
+    }
+}
+
+/// Introspection captured by [`decode_with_trace`]: the full token stream plus
+/// the depth-tagged sequence of parse decisions. Its [`Display`](fmt::Display)
+/// dumps the tokens followed by a parenthesized parse tree.
+#[derive(Debug, Clone, Default)]
+pub struct DecodeTrace {
+    pub tokens: Vec<Token>,
+    pub events: Vec<(usize, TraceEvent)>,
+}
+
+impl fmt::Display for DecodeTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "tokens:")?;
+        for token in &self.tokens {
+            writeln!(f, "  {}", token)?;
+        }
+        writeln!(f, "parse tree:")?;
+        for (depth, event) in &self.events {
+            writeln!(f, "{}{}", "  ".repeat(depth + 1), event)?;
+        }
+        Ok(())
+    }
+}
+
 /// Parser for NEON format
 struct Parser {
     options: NeonDecodeOptions,
     tokens: Vec<Token>,
     current: usize,
     depth: usize,
+    /// Referenceable values in the order they are decoded, addressed by `$<id>`.
+    definitions: Vec<Value>,
+    /// Whether the token stream contains any `$` reference; when it does not,
+    /// composites are not retained in `definitions` at all.
+    references_present: bool,
+    /// Schemas declared once in a `§n=fields` preamble, addressed by `§n` refs
+    /// on the tabular arrays that share a shape.
+    shared_schemas: HashMap<usize, SchemaFields>,
+    /// Recorded parse decisions when tracing is enabled.
+    trace: Option<Vec<(usize, TraceEvent)>>,
 }
 
+/// Reserved key used to carry an unresolved `~` path through the tree until the
+/// whole document is built and the pointer can be resolved against the root.
+const PATH_MARKER: &str = "\u{0}~neon_path";
+
 impl Parser {
     fn new(options: NeonDecodeOptions) -> Self {
         Self {
@@ -283,6 +490,23 @@ impl Parser {
             tokens: Vec::new(),
             current: 0,
             depth: 0,
+            definitions: Vec::new(),
+            references_present: false,
+            shared_schemas: HashMap::new(),
+            trace: None,
+        }
+    }
+
+    /// Enable recording of parse decisions into a [`DecodeTrace`].
+    fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    fn record(&mut self, event: TraceEvent) {
+        let depth = self.depth;
+        if let Some(events) = self.trace.as_mut() {
+            events.push((depth, event));
         }
     }
 
@@ -290,14 +514,45 @@ impl Parser {
         self.tokens = tokens;
         self.current = 0;
         self.depth = 0;
+        self.definitions.clear();
+        self.references_present = self
+            .tokens
+            .iter()
+            .any(|t| t.token_type == TokenType::Reference);
+        self.shared_schemas.clear();
 
+        self.skip_newlines();
+        self.parse_shared_schema_preamble()?;
         self.skip_newlines();
 
         if self.is_at_end() {
             return Ok(Value::Null);
         }
 
-        self.parse_value()
+        let mut root = self.parse_value()?;
+        // Resolve any `~` paths now that the full root is available.
+        let snapshot = root.clone();
+        self.resolve_paths(&mut root, &snapshot, 0)?;
+        Ok(root)
+    }
+
+    /// Consume a leading `§n=fields` preamble, registering each declared schema
+    /// so later `#N§n` arrays can resolve their shape. A bare `§n` (a reference,
+    /// not a declaration) is left in place for [`Parser::parse_array`].
+    fn parse_shared_schema_preamble(&mut self) -> Result<()> {
+        while self.check(TokenType::SchemaRef)
+            && matches!(self.peek_next(), Some(t) if t.token_type == TokenType::String)
+        {
+            let index: usize = self
+                .peek()
+                .and_then(|t| t.value.parse().ok())
+                .ok_or_else(|| NeonError::syntax("Invalid schema reference index", 0, 0))?;
+            self.advance();
+            let fields = self.parse_schema()?;
+            self.shared_schemas.insert(index, fields);
+            self.skip_newlines();
+        }
+        Ok(())
     }
 
     fn parse_value(&mut self) -> Result<Value> {
@@ -344,17 +599,49 @@ impl Parser {
             }
             TokenType::Number => {
                 self.advance();
-                let num: f64 = token.value.parse().unwrap_or(0.0);
-                if num.fract() == 0.0 {
-                    Value::Number(Number::from(num as i64))
+                if self.options.preserve_numbers {
+                    parse_number_exact(&token.raw)
                 } else {
-                    Value::Number(Number::from_f64(num).unwrap_or(Number::from(0)))
+                    let num: f64 = token.value.parse().unwrap_or(0.0);
+                    if num.fract() == 0.0 {
+                        Value::Number(Number::from(num as i64))
+                    } else {
+                        Value::Number(Number::from_f64(num).unwrap_or(Number::from(0)))
+                    }
                 }
             }
             TokenType::String => {
                 self.advance();
                 Value::String(self.expand_abbreviation(&token.value))
             }
+            TokenType::Reference => {
+                self.advance();
+                let id: usize = token.value.parse().map_err(|_| {
+                    NeonError::syntax(
+                        format!("Invalid reference id '{}'", token.value),
+                        token.line,
+                        token.column,
+                    )
+                })?;
+                match self.definitions.get(id) {
+                    Some(value) => value.clone(),
+                    None => {
+                        return Err(NeonError::syntax(
+                            format!("Reference ${} is not yet defined", id),
+                            token.line,
+                            token.column,
+                        ));
+                    }
+                }
+            }
+            TokenType::Path => {
+                self.advance();
+                // Defer resolution: the root is not fully built yet. Path
+                // segments are carried verbatim and are never abbreviation-expanded.
+                let mut marker = Map::new();
+                marker.insert(PATH_MARKER.to_string(), Value::String(token.value));
+                Value::Object(marker)
+            }
             TokenType::ObjectStart => self.parse_object()?,
             TokenType::ArrayStart => self.parse_array()?,
             _ => {
@@ -363,12 +650,20 @@ impl Parser {
             }
         };
 
+        // Register composite values so later `$<id>` references can reuse them,
+        // but only when the document actually contains a `$` — otherwise the
+        // deep clone per composite is pure overhead on reference-free input.
+        if self.references_present && (result.is_object() || result.is_array()) {
+            self.definitions.push(result.clone());
+        }
+
         self.depth -= 1;
         Ok(result)
     }
 
     fn parse_object(&mut self) -> Result<Value> {
         self.expect(TokenType::ObjectStart)?;
+        self.record(TraceEvent::ObjectStart);
         let mut obj = Map::new();
 
         while !self.is_at_end() && !self.check(TokenType::Newline) && !self.check(TokenType::Eof) {
@@ -420,15 +715,38 @@ impl Parser {
 
         self.advance();
         let length: usize = length_token.value.parse().unwrap_or(0) as usize;
+        self.record(TraceEvent::ArrayLength(length));
 
         if length == 0 {
             return Ok(Value::Array(Vec::new()));
         }
 
-        // Check for schema
+        // Check for schema (inline field list, a registered schema name, or a
+        // `§n` reference into the shared-schema preamble).
         let schema = if self.check(TokenType::SchemaStart) {
             self.advance();
-            Some(self.parse_schema()?)
+            let fields = self.parse_schema()?;
+            self.record(TraceEvent::Schema(
+                fields.iter().map(|(name, _)| name.clone()).collect(),
+            ));
+            Some(fields)
+        } else if self.check(TokenType::SchemaRef) {
+            let token = self.peek().unwrap().clone();
+            let index: usize = token.value.parse().map_err(|_| {
+                NeonError::syntax("Invalid schema reference index", token.line, token.column)
+            })?;
+            self.advance();
+            let fields = self.shared_schemas.get(&index).cloned().ok_or_else(|| {
+                NeonError::syntax(
+                    format!("Unknown schema reference §{}", index),
+                    token.line,
+                    token.column,
+                )
+            })?;
+            self.record(TraceEvent::Schema(
+                fields.iter().map(|(name, _)| name.clone()).collect(),
+            ));
+            Some(fields)
         } else {
             None
         };
@@ -447,12 +765,153 @@ impl Parser {
 
         // Multiline array
         if let Some(fields) = schema {
-            self.parse_tabular_rows(length, &fields)
+            // A `|` on the first body line flags a column-major layout.
+            self.skip_newlines();
+            if self.check(TokenType::Columnar) {
+                self.parse_columnar_rows(length, &fields)
+            } else {
+                self.parse_tabular_rows(length, &fields)
+            }
         } else {
             self.parse_list_rows(length)
         }
     }
 
+    /// Parse a column-major tabular body: one `|<mode> ...` line per schema
+    /// field, transposed back into `length` row objects. The counterpart to the
+    /// encoder's `columnar` option.
+    fn parse_columnar_rows(
+        &mut self,
+        length: usize,
+        fields: &[(String, Option<FieldType>)],
+    ) -> Result<Value> {
+        self.record(TraceEvent::TabularRows(length));
+
+        let mut columns: Vec<Vec<Value>> = Vec::with_capacity(fields.len());
+        for _ in fields {
+            self.skip_newlines();
+            self.expect(TokenType::Columnar)?;
+            columns.push(self.parse_column(length)?);
+        }
+
+        let mut result = Vec::with_capacity(length);
+        for row in 0..length {
+            let mut obj = Map::new();
+            for (ci, (field, field_type)) in fields.iter().enumerate() {
+                let value = columns[ci].get(row).cloned().unwrap_or(Value::Null);
+                self.check_field_type(field, field_type, &value, 0, 0)?;
+                obj.insert(field.clone(), value);
+            }
+            result.push(Value::Object(obj));
+        }
+
+        Ok(Value::Array(result))
+    }
+
+    /// Parse a single column line (after its leading `|`), expanding whichever
+    /// of the raw / dictionary / run-length modes it was written in into a flat
+    /// list of `length` cell values.
+    fn parse_column(&mut self, length: usize) -> Result<Vec<Value>> {
+        let mode = match self.peek() {
+            Some(t) if t.token_type == TokenType::String => t.value.clone(),
+            _ => return Err(NeonError::syntax("Expected column mode marker", 0, 0)),
+        };
+        self.advance();
+
+        match mode.as_str() {
+            "R" => {
+                let mut values = Vec::with_capacity(length);
+                for _ in 0..length {
+                    if self.is_at_end() || self.check(TokenType::Newline) {
+                        break;
+                    }
+                    values.push(self.parse_value()?);
+                }
+                Ok(values)
+            }
+            "D" => {
+                let count = self.expect_index("dictionary size")?;
+                let mut dict = Vec::with_capacity(count);
+                for _ in 0..count {
+                    dict.push(self.parse_value()?);
+                }
+                let mut values = Vec::with_capacity(length);
+                for _ in 0..length {
+                    let idx = self.expect_index("dictionary index")?;
+                    let value = dict.get(idx).cloned().ok_or_else(|| {
+                        NeonError::syntax(
+                            format!("Dictionary index {} out of range", idx),
+                            0,
+                            0,
+                        )
+                    })?;
+                    values.push(value);
+                }
+                Ok(values)
+            }
+            "L" => {
+                let mut values = Vec::with_capacity(length);
+                while !self.is_at_end() && !self.check(TokenType::Newline) {
+                    let token = match self.peek() {
+                        Some(t) => t.clone(),
+                        None => break,
+                    };
+                    self.advance();
+                    let (encoded, count) = match token.raw.rsplit_once('*') {
+                        Some((value, count)) => (
+                            value,
+                            count.parse::<usize>().map_err(|_| {
+                                NeonError::syntax(
+                                    format!("Invalid run length '{}'", count),
+                                    token.line,
+                                    token.column,
+                                )
+                            })?,
+                        ),
+                        None => (token.raw.as_str(), 1),
+                    };
+                    let value = self.decode_scalar(encoded)?;
+                    for _ in 0..count {
+                        values.push(value.clone());
+                    }
+                }
+                Ok(values)
+            }
+            other => Err(NeonError::syntax(
+                format!("Unknown column mode '{}'", other),
+                0,
+                0,
+            )),
+        }
+    }
+
+    /// Read one token as a non-negative index (dictionary size or position).
+    fn expect_index(&mut self, what: &str) -> Result<usize> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| NeonError::syntax(format!("Expected {}", what), 0, 0))?;
+        if token.token_type != TokenType::Number {
+            return Err(NeonError::syntax(
+                format!("Expected {}", what),
+                token.line,
+                token.column,
+            ));
+        }
+        self.advance();
+        Ok(token.value.parse::<f64>().unwrap_or(0.0) as usize)
+    }
+
+    /// Decode a single run-length cell, re-lexing its encoded scalar form so it
+    /// passes through the same keyword/abbreviation handling as inline values.
+    fn decode_scalar(&self, encoded: &str) -> Result<Value> {
+        let tokens = Lexer::new(encoded).tokenize();
+        let mut parser = Parser::new(self.options.clone());
+        parser.tokens = tokens;
+        parser.current = 0;
+        parser.parse_value()
+    }
+
     fn parse_named_array(&mut self) -> Result<Value> {
         let name_token = self.peek().unwrap().clone();
         self.advance();
@@ -465,8 +924,27 @@ impl Parser {
         Ok(Value::Object(obj))
     }
 
-    fn parse_schema(&mut self) -> Result<Vec<String>> {
-        let mut fields = Vec::new();
+    fn parse_schema(&mut self) -> Result<SchemaFields> {
+        // A bare `^name` may reference a schema declared in the registry.
+        if let Some(t) = self.peek() {
+            if t.token_type == TokenType::String {
+                let next_is_field = self
+                    .peek_next()
+                    .map(|n| {
+                        n.token_type == TokenType::Comma || n.token_type == TokenType::Colon
+                    })
+                    .unwrap_or(false);
+                if !next_is_field {
+                    if let Some(fields) = self.options.schema_registry.get(&t.value) {
+                        let fields = fields.clone();
+                        self.advance();
+                        return Ok(fields);
+                    }
+                }
+            }
+        }
+
+        let mut fields: SchemaFields = Vec::new();
 
         while !self.is_at_end() {
             let token = match self.peek() {
@@ -475,7 +953,24 @@ impl Parser {
             };
 
             self.advance();
-            fields.push(self.expand_abbreviation(&token.value));
+            let name = self.expand_abbreviation(&token.value);
+
+            // Optional `:>type` annotation on the field.
+            let mut field_type = None;
+            if self.check(TokenType::Colon) {
+                self.advance();
+                if self.check(TokenType::TypePrefix) {
+                    self.advance();
+                }
+                if let Some(type_token) = self.peek() {
+                    if type_token.token_type == TokenType::String {
+                        field_type = FieldType::parse(&type_token.value);
+                        self.advance();
+                    }
+                }
+            }
+
+            fields.push((name, field_type));
 
             if self.check(TokenType::Comma) {
                 self.advance();
@@ -487,7 +982,8 @@ impl Parser {
         Ok(fields)
     }
 
-    fn parse_tabular_rows(&mut self, length: usize, fields: &[String]) -> Result<Value> {
+    fn parse_tabular_rows(&mut self, length: usize, fields: &[(String, Option<FieldType>)]) -> Result<Value> {
+        self.record(TraceEvent::TabularRows(length));
         let mut result = Vec::new();
 
         for _ in 0..length {
@@ -498,11 +994,17 @@ impl Parser {
             }
 
             let mut obj = Map::new();
-            for field in fields {
+            for (field, field_type) in fields {
                 if self.is_at_end() || self.check(TokenType::Newline) {
                     break;
                 }
-                obj.insert(field.clone(), self.parse_value()?);
+                let (line, column) = self
+                    .peek()
+                    .map(|t| (t.line, t.column))
+                    .unwrap_or((0, 0));
+                let value = self.parse_value()?;
+                self.check_field_type(field, field_type, &value, line, column)?;
+                obj.insert(field.clone(), value);
             }
 
             result.push(Value::Object(obj));
@@ -511,7 +1013,70 @@ impl Parser {
         Ok(Value::Array(result))
     }
 
+    /// Validate a decoded cell against its declared field type (strict mode only).
+    fn check_field_type(
+        &self,
+        field: &str,
+        field_type: &Option<FieldType>,
+        value: &Value,
+        line: usize,
+        column: usize,
+    ) -> Result<()> {
+        if self.options.strict {
+            if let Some(ft) = field_type {
+                if !ft.matches(value) {
+                    return Err(NeonError::syntax(
+                        format!(
+                            "field '{}' expected {} but found {}",
+                            field,
+                            ft.name(),
+                            value_kind(value)
+                        ),
+                        line,
+                        column,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a single tabular row (one line's worth of tokens) against a schema.
+    /// Used by the streaming [`RowDecoder`]; shares cell validation with
+    /// [`Parser::parse_tabular_rows`].
+    fn parse_row(
+        &mut self,
+        tokens: Vec<Token>,
+        fields: &[(String, Option<FieldType>)],
+    ) -> Result<Value> {
+        self.tokens = tokens;
+        self.current = 0;
+        self.depth = 0;
+
+        self.skip_newlines();
+        if self.check(TokenType::Indent) {
+            self.advance();
+        }
+        if self.check(TokenType::ListItem) {
+            self.advance();
+        }
+
+        let mut obj = Map::new();
+        for (field, field_type) in fields {
+            if self.is_at_end() || self.check(TokenType::Newline) {
+                break;
+            }
+            let (line, column) = self.peek().map(|t| (t.line, t.column)).unwrap_or((0, 0));
+            let value = self.parse_value()?;
+            self.check_field_type(field, field_type, &value, line, column)?;
+            obj.insert(field.clone(), value);
+        }
+
+        Ok(Value::Object(obj))
+    }
+
     fn parse_list_rows(&mut self, length: usize) -> Result<Value> {
+        self.record(TraceEvent::ListRows(length));
         let mut result = Vec::new();
 
         for _ in 0..length {
@@ -531,6 +1096,77 @@ impl Parser {
         Ok(Value::Array(result))
     }
 
+    /// Replace every deferred `~` path marker with a clone of the value the
+    /// pointer addresses in `root`. Bounded by `max_depth` so cyclic paths
+    /// (a path that resolves to another path) terminate with an error.
+    fn resolve_paths(&self, node: &mut Value, root: &Value, depth: usize) -> Result<()> {
+        if depth > self.options.max_depth {
+            return Err(NeonError::MaxDepth {
+                depth: self.options.max_depth,
+            });
+        }
+
+        if let Value::Object(obj) = node {
+            if obj.len() == 1 {
+                if let Some(Value::String(path)) = obj.get(PATH_MARKER) {
+                    let mut resolved = self.resolve_pointer(path, root)?;
+                    self.resolve_paths(&mut resolved, root, depth + 1)?;
+                    *node = resolved;
+                    return Ok(());
+                }
+            }
+        }
+
+        match node {
+            Value::Array(arr) => {
+                for item in arr.iter_mut() {
+                    self.resolve_paths(item, root, depth + 1)?;
+                }
+            }
+            Value::Object(obj) => {
+                for (_, v) in obj.iter_mut() {
+                    self.resolve_paths(v, root, depth + 1)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Resolve a JSON-Pointer-style path (`a/b/2`) against `root`, indexing
+    /// objects by key and arrays by integer position.
+    fn resolve_pointer(&self, path: &str, root: &Value) -> Result<Value> {
+        let mut current = root;
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            current = match current {
+                Value::Object(obj) => obj.get(segment).ok_or_else(|| {
+                    NeonError::syntax(format!("Unknown path segment '{}'", segment), 0, 0)
+                })?,
+                Value::Array(arr) => {
+                    let idx: usize = segment.parse().map_err(|_| {
+                        NeonError::syntax(
+                            format!("Path segment '{}' is not an array index", segment),
+                            0,
+                            0,
+                        )
+                    })?;
+                    arr.get(idx).ok_or_else(|| {
+                        NeonError::syntax(format!("Path index {} out of range", idx), 0, 0)
+                    })?
+                }
+                _ => {
+                    return Err(NeonError::syntax(
+                        format!("Cannot descend into '{}' via path", segment),
+                        0,
+                        0,
+                    ));
+                }
+            };
+        }
+        Ok(current.clone())
+    }
+
     fn expand_abbreviation(&self, value: &str) -> String {
         if self.options.expand_abbreviations {
             get_expansions()
@@ -631,6 +1267,152 @@ impl NeonDecoder {
     pub fn get_stats(&self) -> &NeonStats {
         &self.stats
     }
+
+    /// Stream a top-level `#N ^schema` tabular array one row at a time.
+    ///
+    /// The returned iterator reads the schema header, then lexes and decodes a
+    /// single row per step without buffering the whole token stream — suitable
+    /// for multi-megabyte dumps the caller wants to process and drop as it
+    /// goes. Per-row abbreviation expansion and type validation match
+    /// [`NeonDecoder::decode`], and [`NeonStats`] is updated incrementally
+    /// (bytes consumed, rows emitted).
+    pub fn decode_rows<R: BufRead>(&mut self, reader: R) -> RowDecoder<'_, R> {
+        self.stats = NeonStats::default();
+        RowDecoder {
+            options: self.options.clone(),
+            stats: &mut self.stats,
+            reader,
+            fields: None,
+            started: false,
+            done: false,
+        }
+    }
+}
+
+/// Iterator over the rows of a streamed tabular NEON array.
+///
+/// Created by [`NeonDecoder::decode_rows`]. Yields one decoded row object per
+/// step, or a [`NeonError`] on the first malformed row.
+pub struct RowDecoder<'a, R: BufRead> {
+    options: NeonDecodeOptions,
+    stats: &'a mut NeonStats,
+    reader: R,
+    fields: Option<Vec<(String, Option<FieldType>)>>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, R: BufRead> RowDecoder<'a, R> {
+    /// Read and parse the `#N ^schema` header line, establishing the field list.
+    fn read_header(&mut self) -> Result<()> {
+        loop {
+            let mut line = String::new();
+            let read = self.reader.read_line(&mut line).map_err(NeonError::Io)?;
+            if read == 0 {
+                // Empty stream: no schema, nothing to yield.
+                self.fields = Some(Vec::new());
+                self.done = true;
+                return Ok(());
+            }
+            self.stats.input_size += read;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let tokens = Lexer::new(&line).tokenize();
+            let mut parser = Parser::new(self.options.clone());
+            parser.tokens = tokens;
+            parser.current = 0;
+
+            // An optional array name precedes the `#` in `name#N^schema`.
+            if parser.check(TokenType::String) {
+                parser.advance();
+            }
+            parser.expect(TokenType::ArrayStart)?;
+
+            let length_token = parser
+                .peek()
+                .cloned()
+                .ok_or_else(|| NeonError::syntax("Expected array length", 0, 0))?;
+            if length_token.token_type != TokenType::Number {
+                return Err(NeonError::syntax("Expected array length", 0, 0));
+            }
+            parser.advance();
+
+            let fields = if parser.check(TokenType::SchemaStart) {
+                parser.advance();
+                parser.parse_schema()?
+            } else {
+                return Err(NeonError::syntax(
+                    "Streaming decode requires a tabular ^schema header",
+                    length_token.line,
+                    length_token.column,
+                ));
+            };
+
+            self.fields = Some(fields);
+            return Ok(());
+        }
+    }
+
+    fn next_row(&mut self) -> Option<Result<Value>> {
+        if self.done {
+            return None;
+        }
+
+        if !self.started {
+            self.started = true;
+            if let Err(e) = self.read_header() {
+                self.done = true;
+                return Some(Err(e));
+            }
+            if self.done {
+                return None;
+            }
+        }
+
+        let fields = self.fields.clone().unwrap_or_default();
+
+        loop {
+            let mut line = String::new();
+            let read = match self.reader.read_line(&mut line) {
+                Ok(n) => n,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(NeonError::Io(e)));
+                }
+            };
+            if read == 0 {
+                self.done = true;
+                return None;
+            }
+            self.stats.input_size += read;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let tokens = Lexer::new(&line).tokenize();
+            let mut parser = Parser::new(self.options.clone());
+            return match parser.parse_row(tokens, &fields) {
+                Ok(row) => {
+                    self.stats.rows += 1;
+                    Some(Ok(row))
+                }
+                Err(e) => {
+                    self.done = true;
+                    Some(Err(e))
+                }
+            };
+        }
+    }
+}
+
+impl<'a, R: BufRead> Iterator for RowDecoder<'a, R> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_row()
+    }
 }
 
 /// Decode a NEON string to JSON Value
@@ -639,6 +1421,37 @@ pub fn decode(input: &str, options: Option<NeonDecodeOptions>) -> Result<Value>
     decoder.decode(input)
 }
 
+/// Tokenize a NEON string without parsing it.
+///
+/// Exposes the lexer so callers debugging why an LLM-produced blob fails to
+/// decode can inspect the raw token stream. Each [`Token`] carries its line and
+/// column, so the list lines up with [`NeonError::syntax`] reports.
+pub fn tokenize(input: &str) -> Vec<Token> {
+    Lexer::new(input).tokenize()
+}
+
+/// Decode a NEON string and return both the value and a [`DecodeTrace`]
+/// recording the token stream and the sequence of parse decisions.
+pub fn decode_with_trace(
+    input: &str,
+    options: Option<NeonDecodeOptions>,
+) -> Result<(Value, DecodeTrace)> {
+    if input.trim().is_empty() {
+        return Ok((Value::Null, DecodeTrace::default()));
+    }
+
+    let tokens = tokenize(input);
+    let mut parser = Parser::new(options.unwrap_or_default()).with_trace();
+    let value = parser.parse(tokens.clone())?;
+
+    let trace = DecodeTrace {
+        tokens,
+        events: parser.trace.take().unwrap_or_default(),
+    };
+
+    Ok((value, trace))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -679,4 +1492,85 @@ mod tests {
         assert!(result.is_array());
         assert_eq!(result.as_array().unwrap().len(), 3);
     }
+
+    #[test]
+    fn test_decode_reference() {
+        // First array is definition $0, reused by the second element.
+        let result = decode("#2\n-#2 1 2\n-$0", None).unwrap();
+        let arr = result.as_array().unwrap();
+        assert_eq!(arr[0], arr[1]);
+    }
+
+    #[test]
+    fn test_decode_forward_reference_errors() {
+        let err = decode("#1\n-$5", None);
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_decode_typed_schema_mismatch() {
+        // `active` is declared bool but the row supplies a number.
+        let err = decode("#1^name,active:>bool\nAlice 3", None);
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_decode_named_schema_registry() {
+        use crate::types::FieldType;
+        let mut opts = NeonDecodeOptions::default();
+        opts.schema_registry.insert(
+            "users",
+            vec![
+                ("name".to_string(), Some(FieldType::String)),
+                ("age".to_string(), Some(FieldType::Number)),
+            ],
+        );
+        let result = decode("#2^users\nAlice 30\nBob 25", Some(opts)).unwrap();
+        let arr = result.as_array().unwrap();
+        assert_eq!(arr[0]["name"], serde_json::json!("Alice"));
+        assert_eq!(arr[1]["age"], serde_json::json!(25));
+    }
+
+    #[test]
+    fn test_decode_path() {
+        let result = decode("@a:1 b:~a", None).unwrap();
+        assert_eq!(result["b"], serde_json::json!(1));
+    }
+
+    #[test]
+    fn test_decode_columnar_rle() {
+        // Two RLE columns transpose back into three row objects.
+        let input = "#3^team,active\n|L Sales*2 Eng\n|L T*2 F";
+        let result = decode(input, None).unwrap();
+        let arr = result.as_array().unwrap();
+        assert_eq!(arr.len(), 3);
+        assert_eq!(arr[0]["team"], serde_json::json!("Sales"));
+        assert_eq!(arr[1]["active"], serde_json::json!(true));
+        assert_eq!(arr[2], serde_json::json!({"team": "Eng", "active": false}));
+    }
+
+    #[test]
+    fn test_decode_columnar_dictionary() {
+        // `|D k <dict> <indices>` expands via the per-column value table.
+        let input = "#3^team\n|D 2 Sales Eng 0 0 1";
+        let result = decode(input, None).unwrap();
+        let arr = result.as_array().unwrap();
+        assert_eq!(arr[0]["team"], serde_json::json!("Sales"));
+        assert_eq!(arr[1]["team"], serde_json::json!("Sales"));
+        assert_eq!(arr[2]["team"], serde_json::json!("Eng"));
+    }
+
+    #[test]
+    fn test_decode_rows_streaming() {
+        let input = "users#2^name,age\nAlice 30\nBob 25\n";
+        let mut decoder = NeonDecoder::new(None);
+        let rows: Vec<_> = decoder
+            .decode_rows(std::io::Cursor::new(input))
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["name"], serde_json::json!("Alice"));
+        assert_eq!(rows[1]["age"], serde_json::json!(25));
+        assert_eq!(decoder.get_stats().rows, 2);
+    }
 }
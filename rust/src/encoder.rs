@@ -1,8 +1,9 @@
 //! NEON Encoder Implementation
 
-use crate::error::Result;
+use crate::error::{NeonError, Result};
 use crate::types::{symbols, get_abbreviations, NeonEncodeOptions, NeonStats};
-use serde_json::Value;
+use serde_json::{Number, Value};
+use std::io::Write;
 use std::time::Instant;
 
 /// Compress a number using K/M/B/T suffixes
@@ -60,13 +61,80 @@ fn compress_number(n: f64, enabled: bool) -> String {
     format!("{}", n)
 }
 
+/// Compress an integer with a K/M/B/T suffix only when the result reconstructs
+/// the original value exactly (at most one decimal place, matching the
+/// decoder's precision); otherwise emit the literal digits. This preserves the
+/// full 64-bit integer range without a lossy `as f64` cast.
+fn compress_integer_lossless(magnitude: u128, sign: &str) -> String {
+    for (mag, suffix) in [
+        (1_000_000_000_000u128, 'T'),
+        (1_000_000_000, 'B'),
+        (1_000_000, 'M'),
+        (1_000, 'K'),
+    ] {
+        if magnitude >= mag && (magnitude * 10) % mag == 0 {
+            let whole = magnitude / mag;
+            let tenth = (magnitude * 10 / mag) % 10;
+            if tenth == 0 {
+                return format!("{}{}{}", sign, whole, suffix);
+            }
+            return format!("{}{}.{}{}", sign, whole, tenth, suffix);
+        }
+    }
+    format!("{}{}", sign, magnitude)
+}
+
+/// Encode a number losslessly: exact integers keep their full precision and
+/// floats are emitted with serde_json's shortest round-tripping representation.
+fn compress_number_lossless(n: &Number, enabled: bool) -> String {
+    if let Some(u) = n.as_u64() {
+        if enabled {
+            return compress_integer_lossless(u as u128, "");
+        }
+        return u.to_string();
+    }
+    if let Some(i) = n.as_i64() {
+        let sign = if i < 0 { "-" } else { "" };
+        if enabled {
+            return compress_integer_lossless((i.unsigned_abs()) as u128, sign);
+        }
+        return i.to_string();
+    }
+    // Floating point: preserve full precision, never suffix-compress. A JSON
+    // number is always finite, so its shortest round-tripping spelling is all
+    // that is needed.
+    n.to_string()
+}
+
+/// Whether an encoded cell is safe to run-length encode: a single,
+/// non-numeric token the lexer will keep intact around its `*` separator.
+fn is_rle_safe(s: &str) -> bool {
+    if s.is_empty() {
+        return false;
+    }
+    if s.contains(' ') || s.contains('*') || s.contains(symbols::COLUMNAR) {
+        return false;
+    }
+    let first = s.chars().next().unwrap();
+    !(first.is_ascii_digit() || first == '-' || first == '.' || first == '"')
+}
+
+/// Render one run-length run as `value` (count 1) or `value*count`.
+fn format_run(value: &str, count: usize) -> String {
+    if count == 1 {
+        value.to_string()
+    } else {
+        format!("{}*{}", value, count)
+    }
+}
+
 /// Check if a string needs to be quoted
 fn needs_quotes(s: &str, delimiter: char) -> bool {
     if s.is_empty() {
         return true;
     }
 
-    let special_chars: Vec<char> = vec![':', '"', '\\', '\n', '\r', '\t'];
+    let special_chars: Vec<char> = vec![':', '"', '\\', '\n', '\r', '\t', symbols::COLUMNAR];
 
     if special_chars.iter().any(|c| s.contains(*c)) {
         return true;
@@ -106,10 +174,27 @@ fn escape_string(s: &str) -> String {
         .replace('\t', "\\t")
 }
 
+/// Dictionary of the distinct tabular schemas found in a document.
+///
+/// Backs the [`NeonEncodeOptions::shared_schemas`] option: each key-set is
+/// assigned a stable index in encounter order, written once in the `§n=fields`
+/// preamble, and referenced as `§n` wherever the schema would otherwise be
+/// spelled out inline.
+#[derive(Default)]
+struct SchemaBundle {
+    /// Raw object key-set -> stable index.
+    index: std::collections::HashMap<Vec<String>, usize>,
+    /// Rendered `field,field,...` header per index, in index order.
+    headers: Vec<String>,
+}
+
 /// NEON Encoder
 pub struct NeonEncoder {
     options: NeonEncodeOptions,
     stats: NeonStats,
+    /// Populated by [`NeonEncoder::encode`] when `shared_schemas` is set; `None`
+    /// otherwise, in which case schemas are always emitted inline.
+    shared: Option<SchemaBundle>,
 }
 
 impl NeonEncoder {
@@ -117,13 +202,33 @@ impl NeonEncoder {
         Self {
             options: options.unwrap_or_default(),
             stats: NeonStats::default(),
+            shared: None,
         }
     }
 
     pub fn encode(&mut self, value: &Value) -> Result<String> {
         let start = Instant::now();
 
-        let result = self.encode_value(value, 0)?;
+        if self.options.shared_schemas {
+            let mut bundle = SchemaBundle::default();
+            self.collect_schemas(value, &mut bundle);
+            self.shared = Some(bundle);
+        }
+
+        let body = self.encode_value(value, 0)?;
+        let result = match &self.shared {
+            Some(bundle) if !bundle.headers.is_empty() => {
+                let preamble = bundle
+                    .headers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, h)| format!("{}{}={}", symbols::SCHEMA_REF, i, h))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                format!("{}{}{}", preamble, self.options.line_ending, body)
+            }
+            _ => body,
+        };
 
         let elapsed = start.elapsed();
         let json_str = serde_json::to_string(value)?;
@@ -146,7 +251,156 @@ impl NeonEncoder {
         &self.stats
     }
 
+    /// Stream the encoded form of `value` directly to a writer instead of
+    /// concatenating it into a `String`. For large tabular arrays this avoids
+    /// holding a second full copy of the document (JSON `Value` + output) in
+    /// memory: each schema header and row line is written to the sink as it is
+    /// produced. Depth is bounded by [`NeonEncodeOptions::max_depth`].
+    pub fn encode_to_writer<W: Write>(&self, value: &Value, writer: &mut W) -> Result<()> {
+        // The tabular cases dominate large documents and are streamed row by
+        // row; everything else is encoded and written in one shot (bounded, as
+        // those shapes are small or shallow).
+        match value {
+            Value::Array(arr) if self.is_tabular(arr) => {
+                self.stream_tabular(None, arr, 0, writer)
+            }
+            Value::Object(obj) if obj.len() == 1 => {
+                let (key, inner) = obj.iter().next().unwrap();
+                match inner {
+                    Value::Array(arr) if self.is_tabular(arr) => {
+                        self.stream_tabular(Some(key), arr, 0, writer)
+                    }
+                    _ => self.write_all(value, writer),
+                }
+            }
+            _ => self.write_all(value, writer),
+        }
+    }
+
+    fn write_all<W: Write>(&self, value: &Value, writer: &mut W) -> Result<()> {
+        let encoded = self.encode_value(value, 0)?;
+        writer.write_all(encoded.as_bytes())?;
+        Ok(())
+    }
+
+    /// Stream a tabular array's schema header and rows directly to the sink.
+    fn stream_tabular<W: Write>(
+        &self,
+        name: Option<&str>,
+        arr: &[Value],
+        depth: usize,
+        writer: &mut W,
+    ) -> Result<()> {
+        if depth > self.options.max_depth {
+            return Err(NeonError::MaxDepth {
+                depth: self.options.max_depth,
+            });
+        }
+
+        let first = arr[0].as_object().unwrap();
+        let fields: Vec<&String> = first.keys().collect();
+        let schema_fields: Vec<String> = fields
+            .iter()
+            .map(|f| self.schema_field_name(f))
+            .collect();
+
+        if let Some(name) = name {
+            write!(writer, "{}", name)?;
+        }
+        write!(
+            writer,
+            "{}{}{}{}",
+            symbols::ARRAY,
+            arr.len(),
+            symbols::SCHEMA,
+            schema_fields.join(",")
+        )?;
+
+        let indent = " ".repeat(self.options.indent * (depth + 1));
+        for item in arr {
+            if let Value::Object(obj) = item {
+                let values: Vec<String> = fields
+                    .iter()
+                    .map(|f| match obj.get(*f) {
+                        Some(v) => self.encode_value(v, depth + 1),
+                        None => Ok(String::new()),
+                    })
+                    .collect::<Result<_>>()?;
+                writer.write_all(self.options.line_ending.as_bytes())?;
+                writer.write_all(indent.as_bytes())?;
+                writer.write_all(values.join(&self.options.delimiter.to_string()).as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Abbreviated schema field name, honoring `abbreviate_fields`.
+    fn schema_field_name(&self, field: &str) -> String {
+        if self.options.abbreviate_fields {
+            get_abbreviations()
+                .get(field)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| field.to_string())
+        } else {
+            field.to_string()
+        }
+    }
+
+    /// Walk the document once, registering every distinct tabular key-set in
+    /// `bundle` in the order it is first encountered (outer arrays before the
+    /// nested arrays inside their rows).
+    fn collect_schemas(&self, value: &Value, bundle: &mut SchemaBundle) {
+        match value {
+            Value::Array(arr) => {
+                if self.is_tabular(arr) {
+                    let first = arr[0].as_object().unwrap();
+                    let keys: Vec<String> = first.keys().cloned().collect();
+                    if !bundle.index.contains_key(&keys) {
+                        let idx = bundle.headers.len();
+                        let header = first
+                            .keys()
+                            .map(|f| self.schema_field_name(f))
+                            .collect::<Vec<_>>()
+                            .join(",");
+                        bundle.index.insert(keys, idx);
+                        bundle.headers.push(header);
+                    }
+                }
+                for item in arr {
+                    self.collect_schemas(item, bundle);
+                }
+            }
+            Value::Object(obj) => {
+                for v in obj.values() {
+                    self.collect_schemas(v, bundle);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Render a tabular array's schema: a `§n` reference when `shared_schemas`
+    /// has registered the key-set, otherwise the inline `^field,...` list.
+    fn schema_header(&self, fields: &[&String]) -> String {
+        if let Some(bundle) = &self.shared {
+            let owned: Vec<String> = fields.iter().map(|f| (*f).clone()).collect();
+            if let Some(idx) = bundle.index.get(&owned) {
+                return format!("{}{}", symbols::SCHEMA_REF, idx);
+            }
+        }
+        let schema_fields: Vec<String> =
+            fields.iter().map(|f| self.schema_field_name(f)).collect();
+        format!("{}{}", symbols::SCHEMA, schema_fields.join(","))
+    }
+
     fn encode_value(&self, value: &Value, depth: usize) -> Result<String> {
+        if depth > self.options.max_depth {
+            return Err(NeonError::MaxDepth {
+                depth: self.options.max_depth,
+            });
+        }
+
         match value {
             Value::Null => {
                 if self.options.compress_nulls {
@@ -167,8 +421,12 @@ impl NeonEncoder {
                 }
             }
             Value::Number(n) => {
-                let num = n.as_f64().unwrap_or(0.0);
-                Ok(compress_number(num, self.options.compress_numbers))
+                if self.options.preserve_numbers || self.options.lossless_numbers {
+                    Ok(compress_number_lossless(n, self.options.compress_numbers))
+                } else {
+                    let num = n.as_f64().unwrap_or(0.0);
+                    Ok(compress_number(num, self.options.compress_numbers))
+                }
             }
             Value::String(s) => Ok(self.encode_string(s)),
             Value::Array(arr) => self.encode_array(arr, depth),
@@ -255,40 +513,29 @@ impl NeonEncoder {
         let first = arr[0].as_object().unwrap();
         let fields: Vec<&String> = first.keys().collect();
 
-        let schema_fields: Vec<String> = fields
-            .iter()
-            .map(|f| {
-                if self.options.abbreviate_fields {
-                    get_abbreviations()
-                        .get(f.as_str())
-                        .map(|s| s.to_string())
-                        .unwrap_or_else(|| f.to_string())
-                } else {
-                    f.to_string()
-                }
-            })
-            .collect();
-
         let mut result = format!(
-            "{}{}{}{}",
+            "{}{}{}",
             symbols::ARRAY,
             arr.len(),
-            symbols::SCHEMA,
-            schema_fields.join(",")
+            self.schema_header(&fields)
         );
 
         let indent = " ".repeat(self.options.indent * (depth + 1));
 
+        if self.options.columnar {
+            result.push_str(&self.encode_columnar_body(&fields, arr, &indent, depth + 1)?);
+            return Ok(result);
+        }
+
         for item in arr {
             if let Value::Object(obj) = item {
                 let values: Vec<String> = fields
                     .iter()
-                    .map(|f| {
-                        obj.get(*f)
-                            .map(|v| self.encode_value(v, depth + 1).unwrap_or_default())
-                            .unwrap_or_default()
+                    .map(|f| match obj.get(*f) {
+                        Some(v) => self.encode_value(v, depth + 1),
+                        None => Ok(String::new()),
                     })
-                    .collect();
+                    .collect::<Result<_>>()?;
 
                 result.push_str(&self.options.line_ending);
                 result.push_str(&indent);
@@ -299,11 +546,122 @@ impl NeonEncoder {
         Ok(result)
     }
 
+    /// Encode a tabular array column-major: one `|<mode> ...` line per field,
+    /// each column written in whichever of the raw, dictionary or run-length
+    /// modes is shortest. The decoder transposes it back on the way in.
+    fn encode_columnar_body(
+        &self,
+        fields: &[&String],
+        arr: &[Value],
+        indent: &str,
+        depth: usize,
+    ) -> Result<String> {
+        let mut out = String::new();
+        for field in fields {
+            let encoded: Vec<String> = arr
+                .iter()
+                .map(|item| match item.as_object().and_then(|o| o.get(*field)) {
+                    Some(v) => self.encode_value(v, depth),
+                    None => Ok(String::new()),
+                })
+                .collect::<Result<_>>()?;
+
+            out.push_str(&self.options.line_ending);
+            out.push_str(indent);
+            out.push_str(&self.encode_column(&encoded));
+        }
+        Ok(out)
+    }
+
+    /// Pick the smallest encoding for one column among raw, dictionary and RLE.
+    fn encode_column(&self, encoded: &[String]) -> String {
+        let delim = self.options.delimiter.to_string();
+        let mut best = format!("{}R {}", symbols::COLUMNAR, encoded.join(&delim));
+
+        if let Some(dict) = self.encode_column_dict(encoded, &delim) {
+            if dict.len() < best.len() {
+                best = dict;
+            }
+        }
+        if let Some(rle) = self.encode_column_rle(encoded, &delim) {
+            if rle.len() < best.len() {
+                best = rle;
+            }
+        }
+        best
+    }
+
+    /// `|D k v0..v{k-1} i0..i{n-1}`: a deduplicated value table followed by one
+    /// index per row. `None` when every value is distinct (no win to be had).
+    fn encode_column_dict(&self, encoded: &[String], delim: &str) -> Option<String> {
+        let mut dict: Vec<&String> = Vec::new();
+        let mut index_of: std::collections::HashMap<&String, usize> =
+            std::collections::HashMap::new();
+        let mut indices: Vec<usize> = Vec::with_capacity(encoded.len());
+        for value in encoded {
+            let idx = *index_of.entry(value).or_insert_with(|| {
+                dict.push(value);
+                dict.len() - 1
+            });
+            indices.push(idx);
+        }
+
+        if dict.len() == encoded.len() {
+            return None;
+        }
+
+        let dict_part = dict
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(delim);
+        let index_part = indices
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(delim);
+        Some(format!(
+            "{}D {}{}{}{}{}",
+            symbols::COLUMNAR,
+            dict.len(),
+            delim,
+            dict_part,
+            delim,
+            index_part
+        ))
+    }
+
+    /// `|L value*count ...`: collapse consecutive equal cells into runs. Only
+    /// offered for simple, non-numeric cells so each run stays a single token
+    /// the lexer won't split on its `*`.
+    fn encode_column_rle(&self, encoded: &[String], delim: &str) -> Option<String> {
+        if encoded.iter().any(|e| !is_rle_safe(e)) {
+            return None;
+        }
+
+        let mut runs: Vec<String> = Vec::new();
+        let mut iter = encoded.iter();
+        let mut current = iter.next()?.clone();
+        let mut count = 1usize;
+        for value in iter {
+            if *value == current {
+                count += 1;
+            } else {
+                runs.push(format_run(&current, count));
+                current = value.clone();
+                count = 1;
+            }
+        }
+        runs.push(format_run(&current, count));
+
+        Some(format!("{}L {}", symbols::COLUMNAR, runs.join(delim)))
+    }
+
     fn encode_primitive_array(&self, arr: &[Value]) -> Result<String> {
         let values: Vec<String> = arr
             .iter()
-            .map(|v| self.encode_value(v, 0).unwrap_or_default())
-            .collect();
+            .map(|v| self.encode_value(v, 0))
+            .collect::<Result<_>>()?;
 
         Ok(format!(
             "{}{} {}",
@@ -348,42 +706,30 @@ impl NeonEncoder {
                     let first = arr[0].as_object().unwrap();
                     let fields: Vec<&String> = first.keys().collect();
 
-                    let schema_fields: Vec<String> = fields
-                        .iter()
-                        .map(|f| {
-                            if self.options.abbreviate_fields {
-                                get_abbreviations()
-                                    .get(f.as_str())
-                                    .map(|s| s.to_string())
-                                    .unwrap_or_else(|| f.to_string())
-                            } else {
-                                f.to_string()
-                            }
-                        })
-                        .collect();
-
                     let mut result = format!(
-                        "{}{}{}{}{}",
+                        "{}{}{}{}",
                         key,
                         symbols::ARRAY,
                         arr.len(),
-                        symbols::SCHEMA,
-                        schema_fields.join(",")
+                        self.schema_header(&fields)
                     );
 
                     let indent = " ".repeat(self.options.indent);
 
+                    if self.options.columnar {
+                        result.push_str(&self.encode_columnar_body(&fields, arr, &indent, 1)?);
+                        return Ok(result);
+                    }
+
                     for item in arr {
                         if let Value::Object(item_obj) = item {
                             let values: Vec<String> = fields
                                 .iter()
-                                .map(|f| {
-                                    item_obj
-                                        .get(*f)
-                                        .map(|v| self.encode_value(v, 1).unwrap_or_default())
-                                        .unwrap_or_default()
+                                .map(|f| match item_obj.get(*f) {
+                                    Some(v) => self.encode_value(v, 1),
+                                    None => Ok(String::new()),
                                 })
-                                .collect();
+                                .collect::<Result<_>>()?;
 
                             result.push_str(&self.options.line_ending);
                             result.push_str(&indent);
@@ -428,6 +774,17 @@ pub fn encode(value: &Value, options: Option<NeonEncodeOptions>) -> Result<Strin
     encoder.encode(value)
 }
 
+/// Encode a JSON value as NEON directly into a writer, streaming large tabular
+/// arrays without building the whole output in memory first.
+pub fn encode_to_writer<W: Write>(
+    value: &Value,
+    writer: &mut W,
+    options: Option<NeonEncodeOptions>,
+) -> Result<()> {
+    let encoder = NeonEncoder::new(options);
+    encoder.encode_to_writer(value, writer)
+}
+
 /// Encode with maximum compression
 pub fn encode_compact(value: &Value) -> Result<String> {
     let options = NeonEncodeOptions {
@@ -464,6 +821,29 @@ mod tests {
         assert_eq!(encode(&json!(2500000), None).unwrap(), "2.5M");
     }
 
+    #[test]
+    fn test_encode_number_lossless() {
+        let opts = NeonEncodeOptions {
+            preserve_numbers: true,
+            ..Default::default()
+        };
+        // Exactly reconstructable values still compress.
+        assert_eq!(encode(&json!(2500000), Some(opts.clone())).unwrap(), "2.5M");
+        // A value that is not a clean multiple keeps its literal digits.
+        assert_eq!(encode(&json!(2512345u64), Some(opts)).unwrap(), "2512345");
+    }
+
+    #[test]
+    fn test_lossless_numbers_preserve_u64_range() {
+        let opts = NeonEncodeOptions {
+            lossless_numbers: true,
+            ..Default::default()
+        };
+        // A u64 beyond i64::MAX must not be corrupted by an `as i64` cast.
+        let big = u64::MAX;
+        assert_eq!(encode(&json!(big), Some(opts)).unwrap(), big.to_string());
+    }
+
     #[test]
     fn test_encode_string() {
         assert_eq!(encode(&json!("hello"), None).unwrap(), "hello");
@@ -476,6 +856,30 @@ mod tests {
         assert_eq!(result, "#3 1 2 3");
     }
 
+    #[test]
+    fn test_encode_to_writer_matches_encode() {
+        let data = json!({
+            "users": [
+                {"id": 1, "name": "Alice"},
+                {"id": 2, "name": "Bob"}
+            ]
+        });
+        let expected = encode(&data, None).unwrap();
+        let mut buf: Vec<u8> = Vec::new();
+        encode_to_writer(&data, &mut buf, None).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_encode_max_depth() {
+        let options = NeonEncodeOptions {
+            max_depth: 2,
+            ..Default::default()
+        };
+        let deep = json!([[[[1]]]]);
+        assert!(encode(&deep, Some(options)).is_err());
+    }
+
     #[test]
     fn test_encode_tabular() {
         let data = json!({
@@ -489,4 +893,59 @@ mod tests {
         assert!(result.contains("Alice"));
         assert!(result.contains("Bob"));
     }
+
+    #[test]
+    fn test_shared_schemas_dedup_repeated_shape() {
+        let opts = NeonEncodeOptions {
+            shared_schemas: true,
+            ..Default::default()
+        };
+        let data = json!({
+            "admins": [{"id": 1, "name": "Alice"}],
+            "users": [{"id": 2, "name": "Bob"}, {"id": 3, "name": "Carol"}]
+        });
+        let result = encode(&data, Some(opts)).unwrap();
+        // The shared key-set is declared once in the preamble...
+        assert!(result.starts_with("§0=id,name"));
+        // ...and both arrays reference it instead of repeating the field list.
+        assert!(result.contains("admins#1§0"));
+        assert!(result.contains("users#2§0"));
+        assert!(!result.contains('^'));
+    }
+
+    #[test]
+    fn test_shared_schemas_roundtrip() {
+        let opts = NeonEncodeOptions {
+            shared_schemas: true,
+            ..Default::default()
+        };
+        let data = json!({
+            "admins": [{"id": 1, "name": "Alice"}],
+            "users": [{"id": 2, "name": "Bob"}, {"id": 3, "name": "Carol"}]
+        });
+        let encoded = encode(&data, Some(opts)).unwrap();
+        // The `§n=fields` preamble and `§n` references decode back to the
+        // original document, not the literal reference text.
+        assert_eq!(crate::decode(&encoded, None).unwrap(), data);
+    }
+
+    #[test]
+    fn test_columnar_roundtrips_and_compresses_repeats() {
+        let opts = NeonEncodeOptions {
+            columnar: true,
+            ..Default::default()
+        };
+        let data = json!({
+            "staff": [
+                {"team": "Sales", "active": true},
+                {"team": "Sales", "active": true},
+                {"team": "Eng", "active": false}
+            ]
+        });
+        let result = encode(&data, Some(opts)).unwrap();
+        // Column-major: one `|` line per field.
+        assert_eq!(result.matches('|').count(), 2);
+        // Round-trips back to the original document.
+        assert_eq!(crate::decode(&result, None).unwrap(), data);
+    }
 }
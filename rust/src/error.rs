@@ -1,5 +1,6 @@
 //! Error types for NEON operations
 
+use serde::ser::{Serialize, SerializeMap, Serializer};
 use thiserror::Error;
 
 /// Errors that can occur during NEON operations
@@ -59,4 +60,67 @@ impl NeonError {
     }
 }
 
+/// Serializes each variant to a stable `kind` tag plus its own fields, with a
+/// constant `severity` of `"error"`, so editors and CI wrappers can parse
+/// failures (mirrors rustc's JSON error output).
+impl Serialize for NeonError {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("severity", "error")?;
+        match self {
+            NeonError::Syntax {
+                message,
+                line,
+                column,
+            } => {
+                map.serialize_entry("kind", "syntax")?;
+                map.serialize_entry("line", line)?;
+                map.serialize_entry("column", column)?;
+                map.serialize_entry("message", message)?;
+            }
+            NeonError::Type { message } => {
+                map.serialize_entry("kind", "type")?;
+                map.serialize_entry("message", message)?;
+            }
+            NeonError::Encode { message } => {
+                map.serialize_entry("kind", "encode")?;
+                map.serialize_entry("message", message)?;
+            }
+            NeonError::Decode { message } => {
+                map.serialize_entry("kind", "decode")?;
+                map.serialize_entry("message", message)?;
+            }
+            NeonError::MaxDepth { depth } => {
+                map.serialize_entry("kind", "max_depth")?;
+                map.serialize_entry("depth", depth)?;
+                map.serialize_entry("message", &self.to_string())?;
+            }
+            NeonError::Io(e) => {
+                map.serialize_entry("kind", "io")?;
+                map.serialize_entry("message", &e.to_string())?;
+            }
+            NeonError::Json(e) => {
+                map.serialize_entry("kind", "json")?;
+                map.serialize_entry("message", &e.to_string())?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl serde::ser::Error for NeonError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        NeonError::encode(msg.to_string())
+    }
+}
+
+impl serde::de::Error for NeonError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        NeonError::type_error(msg.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, NeonError>;
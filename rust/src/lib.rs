@@ -21,10 +21,23 @@
 
 pub mod encoder;
 pub mod decoder;
+pub mod de;
+pub mod memcmp;
+pub mod query;
+pub mod ser;
+pub mod tokenizer;
 pub mod types;
 pub mod error;
 
-pub use encoder::{encode, encode_compact, NeonEncoder};
-pub use decoder::{decode, NeonDecoder};
-pub use types::{NeonEncodeOptions, NeonDecodeOptions, NeonStats};
+pub use encoder::{encode, encode_compact, encode_to_writer, NeonEncoder};
+pub use decoder::{
+    decode, decode_with_trace, tokenize, DecodeTrace, NeonDecoder, RowDecoder, TraceEvent,
+};
+pub use types::{
+    FieldType, NeonDecodeOptions, NeonEncodeOptions, NeonStats, SchemaRegistry,
+};
 pub use error::NeonError;
+
+pub use de::{from_reader, from_str};
+pub use ser::{to_string, to_vec, to_writer};
+pub use tokenizer::{counter_for, Cl100kApprox, HeuristicCounter, TokenCounter};
@@ -0,0 +1,358 @@
+//! Order-preserving binary encoding.
+//!
+//! The text encoder is tuned for token efficiency, not for comparison. This
+//! module produces an alternative byte serialization whose lexicographic
+//! `memcmp` order matches the logical order of the encoded values, so a NEON
+//! blob can be used directly as a sorted key in an embedded KV store (the same
+//! role cozo's `MemCmpEncoder` fills).
+//!
+//! The layout is:
+//!
+//! * every value is prefixed with a one-byte type tag ordered
+//!   `Null < Bool < Number < String < Array < Object`;
+//! * integers and floats share a single 8-byte big-endian ordering key (IEEE
+//!   bits with the sign bit flipped, or all bits flipped when negative) so that
+//!   `-1 < 0 < 1 < 1.5` regardless of how the number was spelled, with an exact
+//!   `i128` trailer for integers so full 64-bit precision round-trips;
+//! * strings use a terminator-escape scheme (`0x00` becomes `0x00 0xFF`, the
+//!   value ends with `0x00 0x01`) so that prefixes sort before their
+//!   extensions;
+//! * arrays and objects are length-free — their elements are concatenated and
+//!   closed with a `0x00` marker that sorts before any element tag, so element
+//!   order alone drives the comparison. Object entries each carry a leading
+//!   `0x01` continuation byte so that a key whose first byte is `0x00` (an empty
+//!   or NUL-leading key) is never mistaken for the closing marker.
+
+use crate::error::{NeonError, Result};
+use serde_json::{Map, Number, Value};
+
+// Type tags, in ascending logical order. `END` sorts before every tag so a
+// shorter sequence compares less than a longer one sharing its prefix.
+const END: u8 = 0x00;
+// Precedes every object entry so the key's first byte can never be mistaken for
+// `END`. Being a constant `> END`, it leaves key order untouched while keeping a
+// shorter object sorting before a longer one that shares its prefix.
+const OBJECT_CONTINUE: u8 = 0x01;
+const TAG_NULL: u8 = 0x01;
+const TAG_BOOL: u8 = 0x02;
+const TAG_NUMBER: u8 = 0x03;
+const TAG_STRING: u8 = 0x04;
+const TAG_ARRAY: u8 = 0x05;
+const TAG_OBJECT: u8 = 0x06;
+
+// Discriminator for the number payload: a float carries only the ordering key,
+// an integer appends its exact value. `FLOAT < INT` so that, at an equal
+// ordering key, `3.0` sorts just before `3`.
+const NUM_FLOAT: u8 = 0x00;
+const NUM_INT: u8 = 0x01;
+
+/// Encode a JSON value into its order-preserving byte form.
+pub fn encode_ordered(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(value, &mut out);
+    out
+}
+
+/// Decode bytes produced by [`encode_ordered`] back into a JSON value.
+pub fn decode_ordered(bytes: &[u8]) -> Result<Value> {
+    let mut cursor = Cursor { bytes, pos: 0 };
+    let value = cursor.read_value()?;
+    if cursor.pos != bytes.len() {
+        return Err(NeonError::decode("trailing bytes after value"));
+    }
+    Ok(value)
+}
+
+fn encode_into(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(TAG_NULL),
+        Value::Bool(b) => {
+            out.push(TAG_BOOL);
+            out.push(if *b { 1 } else { 0 });
+        }
+        Value::Number(n) => {
+            out.push(TAG_NUMBER);
+            encode_number(n, out);
+        }
+        Value::String(s) => {
+            out.push(TAG_STRING);
+            encode_string(s, out);
+        }
+        Value::Array(arr) => {
+            out.push(TAG_ARRAY);
+            for item in arr {
+                encode_into(item, out);
+            }
+            out.push(END);
+        }
+        Value::Object(obj) => {
+            out.push(TAG_OBJECT);
+            for (key, val) in obj {
+                out.push(OBJECT_CONTINUE);
+                encode_string(key, out);
+                encode_into(val, out);
+            }
+            out.push(END);
+        }
+    }
+}
+
+/// Map an `f64` to 8 order-preserving big-endian bytes: flip the sign bit for
+/// non-negative values, flip every bit for negatives.
+fn f64_ordering_key(f: f64) -> [u8; 8] {
+    let bits = f.to_bits();
+    let transformed = if bits & (1 << 63) != 0 {
+        !bits
+    } else {
+        bits ^ (1 << 63)
+    };
+    transformed.to_be_bytes()
+}
+
+fn f64_from_ordering_key(bytes: [u8; 8]) -> f64 {
+    let stored = u64::from_be_bytes(bytes);
+    // A set top bit means the source was non-negative (we set the sign bit).
+    let bits = if stored & (1 << 63) != 0 {
+        stored ^ (1 << 63)
+    } else {
+        !stored
+    };
+    f64::from_bits(bits)
+}
+
+fn encode_number(n: &Number, out: &mut Vec<u8>) {
+    let key = n.as_f64().unwrap_or(0.0);
+    out.extend_from_slice(&f64_ordering_key(key));
+
+    if let Some(exact) = number_as_i128(n) {
+        out.push(NUM_INT);
+        // Sign-flipped big-endian so negatives sort before non-negatives and
+        // ties on the `f64` key (large magnitudes) still order exactly.
+        out.extend_from_slice(&((exact as u128) ^ (1 << 127)).to_be_bytes());
+    } else {
+        out.push(NUM_FLOAT);
+    }
+}
+
+fn number_as_i128(n: &Number) -> Option<i128> {
+    if let Some(u) = n.as_u64() {
+        Some(u as i128)
+    } else {
+        n.as_i64().map(|i| i as i128)
+    }
+}
+
+fn encode_string(s: &str, out: &mut Vec<u8>) {
+    for &byte in s.as_bytes() {
+        if byte == 0x00 {
+            out.push(0x00);
+            out.push(0xFF);
+        } else {
+            out.push(byte);
+        }
+    }
+    out.push(0x00);
+    out.push(0x01);
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn take(&mut self) -> Result<u8> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| NeonError::decode("unexpected end of input"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn take_n(&mut self, n: usize) -> Result<&[u8]> {
+        let end = self.pos + n;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or_else(|| NeonError::decode("unexpected end of input"))?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_value(&mut self) -> Result<Value> {
+        match self.take()? {
+            TAG_NULL => Ok(Value::Null),
+            TAG_BOOL => Ok(Value::Bool(self.take()? != 0)),
+            TAG_NUMBER => self.read_number(),
+            TAG_STRING => Ok(Value::String(self.read_string()?)),
+            TAG_ARRAY => {
+                let mut arr = Vec::new();
+                while self.peek()? != END {
+                    arr.push(self.read_value()?);
+                }
+                self.pos += 1; // consume END
+                Ok(Value::Array(arr))
+            }
+            TAG_OBJECT => {
+                let mut obj = Map::new();
+                while self.peek()? != END {
+                    let marker = self.take()?;
+                    if marker != OBJECT_CONTINUE {
+                        return Err(NeonError::decode(format!(
+                            "invalid object entry marker {marker:#x}"
+                        )));
+                    }
+                    let key = self.read_string()?;
+                    let value = self.read_value()?;
+                    obj.insert(key, value);
+                }
+                self.pos += 1; // consume END
+                Ok(Value::Object(obj))
+            }
+            other => Err(NeonError::decode(format!("unknown type tag {other:#x}"))),
+        }
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.bytes
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| NeonError::decode("unexpected end of input"))
+    }
+
+    fn read_number(&mut self) -> Result<Value> {
+        let key: [u8; 8] = self.take_n(8)?.try_into().unwrap();
+        match self.take()? {
+            NUM_FLOAT => {
+                let f = f64_from_ordering_key(key);
+                Number::from_f64(f)
+                    .map(Value::Number)
+                    .ok_or_else(|| NeonError::decode("non-finite float in key"))
+            }
+            NUM_INT => {
+                let raw: [u8; 16] = self.take_n(16)?.try_into().unwrap();
+                let exact = (u128::from_be_bytes(raw) ^ (1 << 127)) as i128;
+                Ok(int_to_value(exact))
+            }
+            other => Err(NeonError::decode(format!(
+                "unknown number discriminator {other:#x}"
+            ))),
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let mut bytes = Vec::new();
+        loop {
+            match self.take()? {
+                0x00 => match self.take()? {
+                    0x01 => break,
+                    0xFF => bytes.push(0x00),
+                    other => {
+                        return Err(NeonError::decode(format!(
+                            "invalid string escape {other:#x}"
+                        )))
+                    }
+                },
+                byte => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| NeonError::decode("string is not valid UTF-8"))
+    }
+}
+
+/// Rebuild the narrowest JSON number that holds `exact`, preferring `i64` and
+/// falling back to `u64` for values above its range.
+fn int_to_value(exact: i128) -> Value {
+    if let Ok(i) = i64::try_from(exact) {
+        Value::Number(Number::from(i))
+    } else if let Ok(u) = u64::try_from(exact) {
+        Value::Number(Number::from(u))
+    } else {
+        // Out of the JSON integer range; fall back to a float key.
+        Number::from_f64(exact as f64)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn roundtrip(value: Value) {
+        let bytes = encode_ordered(&value);
+        assert_eq!(decode_ordered(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn test_roundtrip_scalars() {
+        roundtrip(json!(null));
+        roundtrip(json!(true));
+        roundtrip(json!(false));
+        roundtrip(json!(0));
+        roundtrip(json!(-17));
+        roundtrip(json!(1_000_000_000_000i64));
+        roundtrip(json!(u64::MAX));
+        roundtrip(json!(3.5));
+        roundtrip(json!("hello"));
+        roundtrip(json!("with\0null"));
+    }
+
+    #[test]
+    fn test_roundtrip_nested() {
+        roundtrip(json!([1, "a", [2, 3], {"k": true}]));
+        roundtrip(json!({"id": 1, "tags": ["x", "y"], "meta": null}));
+    }
+
+    #[test]
+    fn test_roundtrip_empty_and_nul_keys() {
+        // An empty key encodes as the bare string terminator; a NUL-leading key
+        // starts with an escaped `0x00`. Neither may be read as the object's
+        // closing marker.
+        roundtrip(json!({"": 1}));
+        roundtrip(json!({"\u{0}k": 1, "a": 2}));
+    }
+
+    #[test]
+    fn test_type_ordering() {
+        let order = [
+            json!(null),
+            json!(false),
+            json!(42),
+            json!("z"),
+            json!([1]),
+            json!({"a": 1}),
+        ];
+        for pair in order.windows(2) {
+            assert!(encode_ordered(&pair[0]) < encode_ordered(&pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_number_ordering() {
+        let nums = [json!(-1000), json!(-1), json!(0), json!(1), json!(1.5), json!(2)];
+        for pair in nums.windows(2) {
+            assert!(
+                encode_ordered(&pair[0]) < encode_ordered(&pair[1]),
+                "{:?} should sort before {:?}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    #[test]
+    fn test_string_prefix_ordering() {
+        // A prefix must sort before its extension.
+        assert!(encode_ordered(&json!("ab")) < encode_ordered(&json!("abc")));
+        assert!(encode_ordered(&json!("ab")) < encode_ordered(&json!("b")));
+    }
+
+    #[test]
+    fn test_array_length_free_ordering() {
+        assert!(encode_ordered(&json!([1])) < encode_ordered(&json!([1, 0])));
+        assert!(encode_ordered(&json!([1, 2])) < encode_ordered(&json!([1, 3])));
+    }
+}
@@ -0,0 +1,370 @@
+//! Selector/predicate queries over decoded NEON values.
+//!
+//! A [`Selector`] is a compiled sequence of steps that navigates a
+//! [`serde_json::Value`] — the same value produced by [`crate::decode`] — and
+//! collects matching nodes. It composes with the `~` path syntax reserved in
+//! [`crate::types::symbols`] while staying dependency-free.
+//!
+//! # Example
+//!
+//! ```rust
+//! use neon::query::{parse_selector, Select};
+//! use serde_json::json;
+//!
+//! let data = json!({
+//!     "users": [
+//!         {"name": "Alice", "active": true},
+//!         {"name": "Bob", "active": false}
+//!     ]
+//! });
+//!
+//! let selector = parse_selector("users/**[active == true]/name").unwrap();
+//! let names = data.select(&selector);
+//! assert_eq!(names, vec![&json!("Alice")]);
+//! ```
+
+use crate::error::{NeonError, Result};
+use serde_json::Value;
+
+/// A single navigation step in a [`Selector`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// Descend into an object by key.
+    Key(String),
+    /// Index into an array.
+    Index(usize),
+    /// Match every immediate child (object value or array element).
+    Wildcard,
+    /// Match the current node and every descendant.
+    RecursiveDescent,
+    /// Keep only nodes whose sub-path satisfies the predicate.
+    Predicate(Predicate),
+}
+
+/// Comparison operators usable in a predicate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// Substring containment (`~=`), meaningful only for strings.
+    Match,
+}
+
+/// A literal a predicate compares against.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Number(f64),
+    Bool(bool),
+    String(String),
+    Null,
+}
+
+/// A node filter: compare `path` resolved against the candidate to `value`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predicate {
+    pub path: Vec<String>,
+    pub op: CmpOp,
+    pub value: Literal,
+}
+
+/// A compiled selector: an ordered list of steps evaluated left to right.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    pub steps: Vec<Step>,
+}
+
+/// Parse a selector string into a [`Selector`].
+///
+/// Grammar (informal): steps are separated by `/`; a step is a key, an integer
+/// index, `*` (wildcard), `**` (recursive descent), or a predicate
+/// `[path op literal]` attached to the preceding step.
+pub fn parse_selector(input: &str) -> Result<Selector> {
+    let mut steps = Vec::new();
+
+    for raw in input.split('/') {
+        let segment = raw.trim();
+        if segment.is_empty() {
+            continue;
+        }
+
+        // Split an optional trailing `[...]` predicate off the base step.
+        let (base, predicate) = match segment.find('[') {
+            Some(idx) => (&segment[..idx], Some(&segment[idx..])),
+            None => (segment, None),
+        };
+
+        let base = base.trim();
+        if !base.is_empty() {
+            steps.push(parse_base_step(base)?);
+        }
+
+        if let Some(pred) = predicate {
+            steps.push(Step::Predicate(parse_predicate(pred)?));
+        }
+    }
+
+    Ok(Selector { steps })
+}
+
+fn parse_base_step(base: &str) -> Result<Step> {
+    match base {
+        "**" => Ok(Step::RecursiveDescent),
+        "*" => Ok(Step::Wildcard),
+        _ => {
+            if let Ok(idx) = base.parse::<usize>() {
+                Ok(Step::Index(idx))
+            } else {
+                Ok(Step::Key(base.to_string()))
+            }
+        }
+    }
+}
+
+fn parse_predicate(input: &str) -> Result<Predicate> {
+    let inner = input
+        .trim()
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| NeonError::syntax("Unterminated predicate", 0, 0))?
+        .trim();
+
+    // Operators are tried longest-first so `>=` is not read as `>`.
+    let operators = [
+        ("~=", CmpOp::Match),
+        ("==", CmpOp::Eq),
+        ("!=", CmpOp::Ne),
+        (">=", CmpOp::Ge),
+        ("<=", CmpOp::Le),
+        (">", CmpOp::Gt),
+        ("<", CmpOp::Lt),
+    ];
+
+    for (token, op) in operators {
+        if let Some(idx) = inner.find(token) {
+            let lhs = inner[..idx].trim();
+            let rhs = inner[idx + token.len()..].trim();
+            if lhs.is_empty() {
+                return Err(NeonError::syntax("Predicate is missing a path", 0, 0));
+            }
+            let path = lhs.split('.').map(|s| s.trim().to_string()).collect();
+            return Ok(Predicate {
+                path,
+                op,
+                value: parse_literal(rhs),
+            });
+        }
+    }
+
+    Err(NeonError::syntax(
+        format!("Predicate has no comparison operator: {}", inner),
+        0,
+        0,
+    ))
+}
+
+fn parse_literal(s: &str) -> Literal {
+    let s = s.trim();
+    if (s.starts_with('"') && s.ends_with('"') && s.len() >= 2)
+        || (s.starts_with('\'') && s.ends_with('\'') && s.len() >= 2)
+    {
+        return Literal::String(s[1..s.len() - 1].to_string());
+    }
+    match s {
+        "true" => Literal::Bool(true),
+        "false" => Literal::Bool(false),
+        "null" => Literal::Null,
+        _ => match s.parse::<f64>() {
+            Ok(n) => Literal::Number(n),
+            Err(_) => Literal::String(s.to_string()),
+        },
+    }
+}
+
+/// Run a compiled [`Selector`] against a value.
+pub trait Select {
+    /// Collect references to every node matched by the selector.
+    fn select<'a>(&'a self, selector: &Selector) -> Vec<&'a Value>;
+}
+
+impl Select for Value {
+    fn select<'a>(&'a self, selector: &Selector) -> Vec<&'a Value> {
+        let mut current: Vec<&Value> = vec![self];
+
+        for step in &selector.steps {
+            let mut next: Vec<&Value> = Vec::new();
+            match step {
+                Step::Key(key) => {
+                    for node in &current {
+                        if let Value::Object(obj) = node {
+                            if let Some(v) = obj.get(key) {
+                                next.push(v);
+                            }
+                        }
+                    }
+                }
+                Step::Index(idx) => {
+                    for node in &current {
+                        if let Value::Array(arr) = node {
+                            if let Some(v) = arr.get(*idx) {
+                                next.push(v);
+                            }
+                        }
+                    }
+                }
+                Step::Wildcard => {
+                    for node in &current {
+                        match node {
+                            Value::Object(obj) => next.extend(obj.values()),
+                            Value::Array(arr) => next.extend(arr.iter()),
+                            _ => {}
+                        }
+                    }
+                }
+                Step::RecursiveDescent => {
+                    for node in &current {
+                        collect_descendants(node, &mut next);
+                    }
+                }
+                Step::Predicate(pred) => {
+                    for node in &current {
+                        if pred.matches(node) {
+                            next.push(node);
+                        }
+                    }
+                }
+            }
+            current = next;
+        }
+
+        current
+    }
+}
+
+/// Push `node` and all of its descendants (depth-first) onto `out`.
+fn collect_descendants<'a>(node: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(node);
+    match node {
+        Value::Array(arr) => {
+            for item in arr {
+                collect_descendants(item, out);
+            }
+        }
+        Value::Object(obj) => {
+            for v in obj.values() {
+                collect_descendants(v, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Predicate {
+    /// Evaluate the predicate against a candidate node. A type mismatch (e.g.
+    /// comparing a string cell to a number predicate) yields `false` rather
+    /// than an error, so the node is simply skipped.
+    fn matches(&self, node: &Value) -> bool {
+        let mut current = node;
+        for segment in &self.path {
+            current = match current {
+                Value::Object(obj) => match obj.get(segment) {
+                    Some(v) => v,
+                    None => return false,
+                },
+                Value::Array(arr) => match segment.parse::<usize>().ok().and_then(|i| arr.get(i)) {
+                    Some(v) => v,
+                    None => return false,
+                },
+                _ => return false,
+            };
+        }
+        compare(current, self.op, &self.value)
+    }
+}
+
+fn compare(value: &Value, op: CmpOp, literal: &Literal) -> bool {
+    match (value, literal) {
+        (Value::Number(n), Literal::Number(target)) => {
+            let n = match n.as_f64() {
+                Some(n) => n,
+                None => return false,
+            };
+            match op {
+                CmpOp::Eq => n == *target,
+                CmpOp::Ne => n != *target,
+                CmpOp::Gt => n > *target,
+                CmpOp::Ge => n >= *target,
+                CmpOp::Lt => n < *target,
+                CmpOp::Le => n <= *target,
+                CmpOp::Match => false,
+            }
+        }
+        (Value::String(s), Literal::String(target)) => match op {
+            CmpOp::Eq => s == target,
+            CmpOp::Ne => s != target,
+            CmpOp::Match => s.contains(target),
+            _ => false,
+        },
+        (Value::Bool(b), Literal::Bool(target)) => match op {
+            CmpOp::Eq => b == target,
+            CmpOp::Ne => b != target,
+            _ => false,
+        },
+        (Value::Null, Literal::Null) => matches!(op, CmpOp::Eq),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn data() -> Value {
+        json!({
+            "users": [
+                {"name": "Alice", "age": 35, "active": true},
+                {"name": "Bob", "age": 28, "active": false},
+                {"name": "Albert", "age": 41, "active": true}
+            ]
+        })
+    }
+
+    #[test]
+    fn test_child_and_index() {
+        let sel = parse_selector("users/0/name").unwrap();
+        assert_eq!(data().select(&sel), vec![&json!("Alice")]);
+    }
+
+    #[test]
+    fn test_wildcard() {
+        let sel = parse_selector("users/*/name").unwrap();
+        assert_eq!(data().select(&sel).len(), 3);
+    }
+
+    #[test]
+    fn test_predicate_and_recursive_descent() {
+        let sel = parse_selector("users/**[active == true]/name").unwrap();
+        let d = data();
+        let names = d.select(&sel);
+        assert_eq!(names, vec![&json!("Alice"), &json!("Albert")]);
+    }
+
+    #[test]
+    fn test_predicate_numeric_and_match() {
+        let sel = parse_selector("users/*[age > 30]/name").unwrap();
+        assert_eq!(data().select(&sel).len(), 2);
+
+        let sel = parse_selector(r#"users/*[name ~= "Al"]/name"#).unwrap();
+        assert_eq!(data().select(&sel).len(), 2);
+    }
+
+    #[test]
+    fn test_type_mismatch_yields_no_match() {
+        let sel = parse_selector("users/*[name > 30]").unwrap();
+        assert!(data().select(&sel).is_empty());
+    }
+}
@@ -13,6 +13,8 @@ pub mod symbols {
     pub const REFERENCE: char = '$';
     pub const PATH: char = '~';
     pub const TYPE_PREFIX: char = '>';
+    pub const SCHEMA_REF: char = '§';
+    pub const COLUMNAR: char = '|';
     pub const LIST_ITEM: char = '-';
     pub const COLON: char = ':';
     pub const COMMA: char = ',';
@@ -30,8 +32,28 @@ pub struct NeonEncodeOptions {
     pub delimiter: char,
     pub line_ending: String,
     pub indent: usize,
-    pub enable_references: bool,
     pub max_inline_array: usize,
+    /// Maximum nesting depth before encoding fails with [`crate::NeonError::MaxDepth`],
+    /// guarding against stack exhaustion on adversarial input.
+    pub max_depth: usize,
+    /// Only apply magnitude suffixes when the exact value is reconstructable,
+    /// emitting literal digits otherwise and preserving full integer precision.
+    pub preserve_numbers: bool,
+    /// Encode integers without the lossy `as f64` round-trip: branch on
+    /// `u64`/`i64` first and suffix-compress only reversible values. Floats are
+    /// written in their shortest round-tripping form and never suffix-compressed;
+    /// there is no spelling for `NaN` or `Infinity`, as a JSON number is always
+    /// finite.
+    pub lossless_numbers: bool,
+    /// Declare each distinct tabular schema once in a `§n=fields` preamble and
+    /// replace every array's inline `^field,...` list with a short `§n`
+    /// reference, shrinking documents dominated by many records of the same
+    /// shape.
+    pub shared_schemas: bool,
+    /// Encode tabular arrays column-major instead of row-major, applying
+    /// per-column dictionary substitution or run-length encoding (whichever is
+    /// smallest) to squeeze out the redundancy of low-cardinality columns.
+    pub columnar: bool,
 }
 
 impl Default for NeonEncodeOptions {
@@ -46,8 +68,12 @@ impl Default for NeonEncodeOptions {
             delimiter: ' ',
             line_ending: "\n".to_string(),
             indent: 2,
-            enable_references: false,
             max_inline_array: 10,
+            max_depth: 128,
+            preserve_numbers: false,
+            lossless_numbers: false,
+            shared_schemas: false,
+            columnar: false,
         }
     }
 }
@@ -66,6 +92,10 @@ pub struct NeonDecodeOptions {
     pub strict: bool,
     pub expand_abbreviations: bool,
     pub max_depth: usize,
+    pub schema_registry: SchemaRegistry,
+    /// Decode numbers into a precision-preserving representation instead of
+    /// collapsing every value through `f64`.
+    pub preserve_numbers: bool,
 }
 
 impl Default for NeonDecodeOptions {
@@ -74,8 +104,87 @@ impl Default for NeonDecodeOptions {
             strict: true,
             expand_abbreviations: true,
             max_depth: 100,
+            schema_registry: SchemaRegistry::new(),
+            preserve_numbers: false,
+        }
+    }
+}
+
+/// Declared type of a schema field, parsed from a `>type` annotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+impl FieldType {
+    /// Parse a `>type` annotation (e.g. `>number`). The leading `>` is optional.
+    pub fn parse(s: &str) -> Option<FieldType> {
+        let s = s.strip_prefix(symbols::TYPE_PREFIX).unwrap_or(s);
+        match s {
+            "string" | "str" => Some(FieldType::String),
+            "number" | "num" | "int" | "float" => Some(FieldType::Number),
+            "bool" | "boolean" => Some(FieldType::Bool),
+            "null" => Some(FieldType::Null),
+            _ => None,
+        }
+    }
+
+    /// Whether a decoded value satisfies this declared type.
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        match self {
+            FieldType::String => value.is_string(),
+            FieldType::Number => value.is_number(),
+            FieldType::Bool => value.is_boolean(),
+            FieldType::Null => value.is_null(),
+        }
+    }
+
+    /// Human-readable name, used in type-mismatch diagnostics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldType::String => "string",
+            FieldType::Number => "number",
+            FieldType::Bool => "bool",
+            FieldType::Null => "null",
+        }
+    }
+}
+
+/// An ordered list of `(field_name, declared_type)` pairs making up one schema.
+pub type SchemaFields = Vec<(String, Option<FieldType>)>;
+
+/// Named, reusable schema definitions looked up by name during decode.
+///
+/// Schemas are registered through [`NeonDecodeOptions::schema_registry`]; a
+/// tabular array then references one by name (`#2^users`) instead of repeating
+/// the field list. A document cannot add a schema to the registry itself.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    defs: HashMap<String, SchemaFields>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self {
+            defs: HashMap::new(),
         }
     }
+
+    /// Register a named schema, replacing any previous definition.
+    pub fn insert(&mut self, name: impl Into<String>, fields: SchemaFields) {
+        self.defs.insert(name.into(), fields);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SchemaFields> {
+        self.defs.get(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
 }
 
 /// Statistics from encoding/decoding operations
@@ -89,6 +198,8 @@ pub struct NeonStats {
     pub output_tokens: usize,
     pub encode_time_ms: f64,
     pub decode_time_ms: f64,
+    /// Number of rows emitted by the streaming decoder.
+    pub rows: usize,
 }
 
 /// Token types for lexer
@@ -102,6 +213,11 @@ pub enum TokenType {
     Newline,
     Indent,
     ListItem,
+    TypePrefix,
+    Columnar,
+    SchemaRef,
+    Reference,
+    Path,
     Null,
     Boolean,
     Number,
@@ -119,6 +235,19 @@ pub struct Token {
     pub column: usize,
 }
 
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<12} {:>3}:{:<3} {:?}",
+            format!("{:?}", self.token_type),
+            self.line,
+            self.column,
+            self.value
+        )
+    }
+}
+
 /// Field abbreviations
 pub fn get_abbreviations() -> HashMap<&'static str, &'static str> {
     let mut map = HashMap::new();